@@ -0,0 +1,167 @@
+//! # Atomic Modesetting
+//!
+//! The legacy `crtc::set` path re-applies a full modeset and only touches a
+//! single CRTC at a time. Atomic modesetting instead lets a caller stage
+//! property changes across any number of CRTCs, connectors, and planes and
+//! apply them together in a single, all-or-nothing `DRM_IOCTL_MODE_ATOMIC`
+//! call.
+//!
+//! An [`AtomicReq`] accumulates `(object, property, value)` triples with
+//! [`AtomicReq::add_property`], which [`commit`] then submits. Modes are
+//! themselves set atomically through a blob property: turn a [`Mode`] into
+//! a handle with [`create_property_blob`] and assign it to a CRTC's
+//! `MODE_ID` property.
+//!
+//! [`AtomicReq`]: AtomicReq.t.html
+//! [`AtomicReq::add_property`]: AtomicReq.t.html#method.add_property
+//! [`commit`]: fn.commit.html
+//! [`Mode`]: ../struct.Mode.html
+//! [`create_property_blob`]: fn.create_property_blob.html
+
+use std::mem;
+
+use control::{self, ResourceHandle};
+use control::property::Handle as PropHandle;
+use result::*;
+use ffi;
+
+/// Only validates the request against the current hardware state without
+/// applying it.
+pub const TEST_ONLY: u32 = ffi::DRM_MODE_ATOMIC_TEST_ONLY;
+/// Requests that the commit not block until it completes.
+pub const NONBLOCK: u32 = ffi::DRM_MODE_ATOMIC_NONBLOCK;
+/// Allows this commit to perform a full modeset, which may briefly blank
+/// the display.
+pub const ALLOW_MODESET: u32 = ffi::DRM_MODE_ATOMIC_ALLOW_MODESET;
+
+/// A [`ResourceHandle`] for a property blob, such as a [`Mode`] uploaded
+/// with [`create_property_blob`].
+///
+/// [`ResourceHandle`]: ../ResourceHandle.t.html
+/// [`Mode`]: ../struct.Mode.html
+/// [`create_property_blob`]: fn.create_property_blob.html
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct BlobHandle(control::RawHandle);
+
+impl ResourceHandle for BlobHandle {
+    fn from_raw(raw: control::RawHandle) -> Self {
+        BlobHandle(raw)
+    }
+
+    fn as_raw(&self) -> control::RawHandle {
+        self.0
+    }
+}
+
+impl ::std::fmt::Debug for BlobHandle {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+        write!(f, "atomic::BlobHandle({})", self.0)
+    }
+}
+
+/// A builder that accumulates the property changes for an atomic commit.
+///
+/// Properties are grouped by the object they belong to as they're added,
+/// matching the parallel-array layout `drm_mode_atomic` expects.
+#[derive(Debug, Clone, Default)]
+pub struct AtomicReq {
+    objects: Vec<(control::RawHandle, Vec<(control::RawHandle, u64)>)>
+}
+
+impl AtomicReq {
+    /// Creates an empty atomic request.
+    pub fn new() -> Self {
+        AtomicReq { objects: Vec::new() }
+    }
+
+    /// Stages a single property change on `object`.
+    ///
+    /// `object` is the handle of a CRTC, connector, or plane, and
+    /// `property` the handle of one of the properties
+    /// [`property::LoadProperties`] enumerated for it.
+    ///
+    /// [`property::LoadProperties`]: ../property/trait.LoadProperties.html
+    pub fn add_property<H>(&mut self, object: H, property: PropHandle, value: u64)
+        where H: ResourceHandle {
+
+        let object = object.as_raw();
+        let property = property.as_raw();
+
+        match self.objects.iter_mut().find(|entry| entry.0 == object) {
+            Some(entry) => entry.1.push((property, value)),
+            None => self.objects.push((object, vec![(property, value)]))
+        }
+    }
+}
+
+/// Applies every property change staged in `req` in a single, all-or-nothing
+/// commit.
+///
+/// `flags` is any combination of [`TEST_ONLY`], [`NONBLOCK`], and
+/// [`ALLOW_MODESET`]. With [`TEST_ONLY`] set, the kernel validates the
+/// request against the current hardware state without applying it.
+///
+/// [`TEST_ONLY`]: constant.TEST_ONLY.html
+/// [`NONBLOCK`]: constant.NONBLOCK.html
+/// [`ALLOW_MODESET`]: constant.ALLOW_MODESET.html
+pub fn commit<T>(device: &T, req: &AtomicReq, flags: u32) -> Result<()>
+    where T: control::Device {
+
+    let objs: Vec<u32> = req.objects.iter().map(|o| o.0).collect();
+    let count_props: Vec<u32> = req.objects.iter().map(|o| o.1.len() as u32).collect();
+
+    let mut props: Vec<u32> = Vec::new();
+    let mut values: Vec<u64> = Vec::new();
+    for &(_, ref object_props) in &req.objects {
+        for &(prop, value) in object_props {
+            props.push(prop);
+            values.push(value);
+        }
+    }
+
+    let mut raw: ffi::drm_mode_atomic = Default::default();
+    raw.flags = flags;
+    raw.count_objs = objs.len() as u32;
+    raw.objs_ptr = objs.as_ptr() as u64;
+    raw.count_props_ptr = count_props.as_ptr() as u64;
+    raw.props_ptr = props.as_ptr() as u64;
+    raw.prop_values_ptr = values.as_ptr() as u64;
+
+    unsafe {
+        try!(ffi::ioctl_mode_atomic(device.as_raw_fd(), &mut raw));
+    }
+
+    Ok(())
+}
+
+/// Uploads `mode` as a property blob, returning a handle that can be
+/// assigned to an object's blob property, such as a CRTC's `MODE_ID`.
+pub fn create_property_blob<T>(device: &T, mode: &control::Mode) -> Result<BlobHandle>
+    where T: control::Device {
+
+    let mut raw: ffi::drm_mode_create_blob = Default::default();
+    raw.data = &mode.mode as *const _ as u64;
+    raw.length = mem::size_of::<ffi::drm_mode_modeinfo>() as u32;
+
+    unsafe {
+        try!(ffi::ioctl_mode_createpropblob(device.as_raw_fd(), &mut raw));
+    }
+
+    Ok(BlobHandle::from_raw(raw.blob_id))
+}
+
+/// Frees a property blob previously created with [`create_property_blob`].
+///
+/// [`create_property_blob`]: fn.create_property_blob.html
+pub fn destroy_property_blob<T>(device: &T, blob: BlobHandle) -> Result<()>
+    where T: control::Device {
+
+    let mut raw: ffi::drm_mode_destroy_blob = Default::default();
+    raw.blob_id = blob.as_raw();
+
+    unsafe {
+        try!(ffi::ioctl_mode_destroypropblob(device.as_raw_fd(), &mut raw));
+    }
+
+    Ok(())
+}
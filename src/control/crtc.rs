@@ -11,6 +11,8 @@
 //! Each CRTC has a built in plane, which can be attached to a framebuffer. It
 //! can also use pixel data from other planes to perform hardware compositing.
 
+use std::io;
+
 use ::{Dimensions, iPoint};
 use buffer;
 use control::{self, ResourceHandle, ResourceInfo};
@@ -41,7 +43,7 @@ pub struct Handle(control::RawHandle);
 pub struct Info {
     handle: Handle,
     position: (u32, u32),
-    // TODO: mode
+    mode: Option<control::Mode>,
     fb: control::framebuffer::Handle,
     gamma_length: u32
 }
@@ -73,9 +75,15 @@ impl ResourceInfo for Info {
                 try!(ffi::ioctl_mode_getcrtc(device.as_raw_fd(), &mut raw));
             }
 
+            let mode = match raw.mode_valid {
+                0 => None,
+                _ => Some(control::Mode { mode: raw.mode })
+            };
+
             Self {
                 handle: handle,
                 position: (raw.x, raw.y),
+                mode: mode,
                 fb: control::framebuffer::Handle::from_raw(raw.fb_id),
                 gamma_length: raw.gamma_size
             }
@@ -87,6 +95,33 @@ impl ResourceInfo for Info {
     fn handle(&self) -> Self::Handle { self.handle }
 }
 
+impl Info {
+    /// The mode this CRTC is currently driving, if it has an active mode.
+    pub fn mode(&self) -> Option<control::Mode> {
+        self.mode
+    }
+}
+
+/// Given the list of modes a connector supports, returns the one matching
+/// the CRTC's currently active resolution and refresh rate, if any.
+///
+/// This is useful for a compositor restoring display state, for example
+/// after a VT-switch, that wants to re-apply the exact mode that was
+/// active before rather than falling back to the connector's preferred
+/// mode.
+pub fn matching_mode(info: &Info, modes: &[control::Mode]) -> Option<control::Mode> {
+    let active = match info.mode {
+        Some(m) => m,
+        None => return None
+    };
+
+    modes.iter().find(|m| {
+        m.mode.hdisplay == active.mode.hdisplay &&
+        m.mode.vdisplay == active.mode.vdisplay &&
+        m.mode.vrefresh == active.mode.vrefresh
+    }).cloned()
+}
+
 /// Attaches a framebuffer to a CRTC's built-in plane, attaches the CRTC to
 /// a connector, and sets the CRTC's mode to output the pixel data.
 pub fn set<T>(device: &T, handle: Handle, fb: FBHandle, cons: &[ConHandle],
@@ -117,6 +152,46 @@ pub fn set<T>(device: &T, handle: Handle, fb: FBHandle, cons: &[ConHandle],
     Ok(())
 }
 
+/// Flags controlling the behavior of [`page_flip`].
+///
+/// [`page_flip`]: fn.page_flip.html
+pub const PAGE_FLIP_EVENT: u32 = ffi::DRM_MODE_PAGE_FLIP_EVENT;
+/// Performs the flip as soon as possible, rather than waiting for the next
+/// vblank.
+pub const PAGE_FLIP_ASYNC: u32 = ffi::DRM_MODE_PAGE_FLIP_ASYNC;
+
+/// Schedules a framebuffer change on a CRTC without the full modeset that
+/// [`set`] performs.
+///
+/// The new framebuffer must match the dimensions and pixel format of the
+/// one currently being scanned out, and only one flip may be pending on a
+/// given CRTC at a time - a second flip issued before the first completes
+/// is rejected by the kernel.
+///
+/// If `flags` includes [`PAGE_FLIP_EVENT`], `user_data` is returned
+/// unchanged in the [`control::Event::PageFlip`] delivered once the flip
+/// completes, allowing the caller to correlate the event with the request
+/// that triggered it.
+///
+/// [`set`]: fn.set.html
+/// [`PAGE_FLIP_EVENT`]: constant.PAGE_FLIP_EVENT.html
+/// [`control::Event::PageFlip`]: ../event/enum.Event.html
+pub fn page_flip<T>(device: &T, handle: Handle, fb: FBHandle, flags: u32, user_data: u64) -> Result<()>
+    where T: control::Device {
+
+    let mut raw: ffi::drm_mode_crtc_page_flip = Default::default();
+    raw.crtc_id = handle.as_raw();
+    raw.fb_id = fb.as_raw();
+    raw.flags = flags;
+    raw.user_data = user_data;
+
+    unsafe {
+        try!(ffi::ioctl_mode_page_flip(device.as_raw_fd(), &mut raw));
+    }
+
+    Ok(())
+}
+
 pub fn set_cursor<T>(device: &T, handle: Handle, bo: buffer::Id, dimensions: Dimensions) -> Result<()>
     where T: control::Device {
 
@@ -169,6 +244,78 @@ pub fn move_cursor<T>(device: &T, handle: Handle, to: iPoint) -> Result<()>
     Ok(())
 }
 
+/// The color lookup table used by a CRTC to perform gamma correction.
+///
+/// Each channel holds exactly [`Info`]'s `gamma_length` entries, one per step
+/// of the CRTC's lookup table.
+///
+/// [`Info`]: Info.t.html
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GammaRamp {
+    pub red: Vec<u16>,
+    pub green: Vec<u16>,
+    pub blue: Vec<u16>
+}
+
+/// Reads the gamma ramp currently programmed into a CRTC's color lookup
+/// table.
+pub fn gamma<T>(device: &T, handle: Handle) -> Result<GammaRamp>
+    where T: control::Device {
+
+    let info = try!(Info::load_from_device(device, handle));
+    let size = info.gamma_length as usize;
+
+    let mut red = vec![0u16; size];
+    let mut green = vec![0u16; size];
+    let mut blue = vec![0u16; size];
+
+    let mut raw: ffi::drm_mode_crtc_lut = Default::default();
+    raw.crtc_id = handle.as_raw();
+    raw.gamma_size = info.gamma_length;
+    raw.red = red.as_mut_ptr() as u64;
+    raw.green = green.as_mut_ptr() as u64;
+    raw.blue = blue.as_mut_ptr() as u64;
+
+    unsafe {
+        try!(ffi::ioctl_mode_getgamma(device.as_raw_fd(), &mut raw));
+    }
+
+    Ok(GammaRamp { red: red, green: green, blue: blue })
+}
+
+/// Programs a CRTC's color lookup table.
+///
+/// The ramp's three channels must each have exactly as many entries as the
+/// CRTC's `gamma_size`, as returned by [`Info`]. Use [`gamma`] to fetch a
+/// ramp of the correct length to modify in place.
+///
+/// [`Info`]: Info.t.html
+/// [`gamma`]: fn.gamma.html
+pub fn set_gamma<T>(device: &T, handle: Handle, ramp: &GammaRamp) -> Result<()>
+    where T: control::Device {
+
+    let info = try!(Info::load_from_device(device, handle));
+    let size = info.gamma_length as usize;
+
+    if ramp.red.len() != size || ramp.green.len() != size || ramp.blue.len() != size {
+        return Err(io::Error::new(io::ErrorKind::InvalidInput,
+            "gamma ramp length does not match the CRTC's gamma_size").into());
+    }
+
+    let mut raw: ffi::drm_mode_crtc_lut = Default::default();
+    raw.crtc_id = handle.as_raw();
+    raw.gamma_size = info.gamma_length;
+    raw.red = ramp.red.as_ptr() as u64;
+    raw.green = ramp.green.as_ptr() as u64;
+    raw.blue = ramp.blue.as_ptr() as u64;
+
+    unsafe {
+        try!(ffi::ioctl_mode_setgamma(device.as_raw_fd(), &mut raw));
+    }
+
+    Ok(())
+}
+
 impl ::std::fmt::Debug for Handle {
     fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
         write!(f, "crtc::Handle({})", self.0)
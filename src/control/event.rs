@@ -0,0 +1,100 @@
+//! # Events
+//!
+//! Some operations, such as a CRTC page flip requested with
+//! [`crtc::PAGE_FLIP_EVENT`], complete asynchronously. The kernel signals
+//! their completion by making the device's file descriptor readable and
+//! writing one or more `drm_event` records to it. This module reads those
+//! records and decodes them into [`Event`] values.
+//!
+//! [`crtc::PAGE_FLIP_EVENT`]: ../crtc/constant.PAGE_FLIP_EVENT.html
+//! [`Event`]: enum.Event.html
+
+use std::io;
+use std::mem;
+use std::ptr;
+
+use libc;
+
+use control;
+use result::*;
+use ffi;
+
+/// A single decoded DRM event.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Event {
+    /// A page flip scheduled with `crtc::page_flip` has completed.
+    PageFlip {
+        /// The `user_data` token passed to the `crtc::page_flip` call that
+        /// requested this event.
+        user_data: u64,
+        /// The sequence number of the vblank the flip completed on.
+        frame: u32,
+        /// Kernel timestamp of the completion, as `(seconds, microseconds)`.
+        time: (u32, u32)
+    },
+    /// A vblank event occurred.
+    VBlank {
+        user_data: u64,
+        frame: u32,
+        time: (u32, u32)
+    }
+}
+
+/// Reads and decodes the events currently pending on a device's file
+/// descriptor.
+///
+/// The fd should already be known to be readable, typically by polling it,
+/// as this performs a single blocking `read()`. Unrecognized event types
+/// are silently skipped.
+pub fn read_events<T>(device: &T) -> Result<Vec<Event>>
+    where T: control::Device {
+
+    let mut buf = [0u8; 1024];
+    let n = unsafe {
+        libc::read(device.as_raw_fd(), buf.as_mut_ptr() as *mut libc::c_void, buf.len())
+    };
+
+    if n < 0 {
+        return Err(io::Error::last_os_error().into());
+    }
+
+    Ok(decode_events(&buf[..n as usize]))
+}
+
+fn decode_events(mut buf: &[u8]) -> Vec<Event> {
+    let mut events = Vec::new();
+    let header_size = mem::size_of::<ffi::drm_event>();
+
+    while buf.len() >= header_size {
+        let header: ffi::drm_event = unsafe {
+            ptr::read_unaligned(buf.as_ptr() as *const ffi::drm_event)
+        };
+
+        let event_len = header.length as usize;
+        if event_len < header_size || event_len > buf.len() {
+            break;
+        }
+
+        if (header.type_ == ffi::DRM_EVENT_FLIP_COMPLETE ||
+            header.type_ == ffi::DRM_EVENT_VBLANK) &&
+           event_len >= mem::size_of::<ffi::drm_event_vblank>() {
+
+            let vblank: ffi::drm_event_vblank = unsafe {
+                ptr::read_unaligned(buf.as_ptr() as *const ffi::drm_event_vblank)
+            };
+
+            let time = (vblank.tv_sec, vblank.tv_usec);
+            let event = if header.type_ == ffi::DRM_EVENT_FLIP_COMPLETE {
+                Event::PageFlip { user_data: vblank.user_data, frame: vblank.sequence, time: time }
+            } else {
+                Event::VBlank { user_data: vblank.user_data, frame: vblank.sequence, time: time }
+            };
+
+            events.push(event);
+        }
+
+        buf = &buf[event_len..];
+    }
+
+    events
+}
@@ -0,0 +1,104 @@
+//! # Framebuffer
+//!
+//! A framebuffer is a region of memory that can be attached to a CRTC's
+//! built-in plane (or another plane) and scanned out to a connector.
+
+use std::error;
+use std::fmt;
+use std::io;
+
+use libc;
+
+use control::{self, ResourceHandle};
+use result::*;
+use ffi;
+
+/// A [`ResourceHandle`] for a framebuffer.
+///
+/// [`ResourceHandle`]: ../ResourceHandle.t.html
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct Handle(control::RawHandle);
+
+impl ResourceHandle for Handle {
+    fn from_raw(raw: control::RawHandle) -> Self {
+        Handle(raw)
+    }
+
+    fn as_raw(&self) -> control::RawHandle {
+        self.0
+    }
+}
+
+impl ::std::fmt::Debug for Handle {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+        write!(f, "framebuffer::Handle({})", self.0)
+    }
+}
+
+/// A rectangle, in framebuffer pixel coordinates, marking a region that has
+/// changed since the framebuffer was last scanned out.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ClipRect {
+    pub x1: u16,
+    pub y1: u16,
+    pub x2: u16,
+    pub y2: u16
+}
+
+/// Marker returned by [`mark_dirty`] when the driver doesn't implement
+/// `DRM_IOCTL_MODE_DIRTYFB` (the ioctl fails with `ENOSYS`).
+///
+/// Unlike a bare `ErrorKind::Other`, this can be matched on reliably, for
+/// example via `err.get_ref().map_or(false, |e| e.is::<DirtyNotSupported>())`,
+/// so a caller can fall back to a full flip only for this specific failure.
+///
+/// [`mark_dirty`]: fn.mark_dirty.html
+#[derive(Debug)]
+pub struct DirtyNotSupported;
+
+impl fmt::Display for DirtyNotSupported {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "driver does not support dirty-region reporting")
+    }
+}
+
+impl error::Error for DirtyNotSupported {
+    fn description(&self) -> &str {
+        "driver does not support dirty-region reporting"
+    }
+}
+
+/// Tells the driver which regions of a framebuffer have changed since it was
+/// last scanned out, so it only needs to re-read the damaged rectangles
+/// rather than the whole buffer.
+///
+/// This is primarily useful for shadow-buffer and virtual/streaming GPUs
+/// (QXL, virtio-gpu). Callers should draw into the buffer currently
+/// attached to a CRTC's scanout, then report the changed regions here
+/// instead of re-flipping the whole framebuffer.
+///
+/// If the driver doesn't support dirty-region reporting, this returns an
+/// error distinguishable from other failures (see [`DirtyNotSupported`]) so
+/// callers can fall back to a full flip instead.
+///
+/// [`DirtyNotSupported`]: struct.DirtyNotSupported.html
+pub fn mark_dirty<T>(device: &T, fb: Handle, clips: &[ClipRect]) -> Result<()>
+    where T: control::Device {
+
+    let mut raw: ffi::drm_mode_fb_dirty_cmd = Default::default();
+    raw.fb_id = fb.as_raw();
+    raw.clips_ptr = clips.as_ptr() as u64;
+    raw.num_clips = clips.len() as u32;
+
+    let res = unsafe {
+        ffi::ioctl_mode_dirtyfb(device.as_raw_fd(), &mut raw)
+    };
+
+    match res {
+        Err(ref e) if e.raw_os_error() == Some(libc::ENOSYS) =>
+            Err(io::Error::new(io::ErrorKind::Other, DirtyNotSupported).into()),
+        Err(e) => Err(e.into()),
+        Ok(_) => Ok(())
+    }
+}
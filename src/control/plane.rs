@@ -0,0 +1,150 @@
+//! # Plane
+//!
+//! A plane is a source of pixel data that a CRTC can composite onto its
+//! scanout alongside its own built-in plane. Overlay planes are commonly
+//! used for video, and cursor planes for hardware cursors, both without the
+//! cost of a full modeset.
+
+use control::{self, ResourceHandle, ResourceInfo};
+use control::framebuffer::Handle as FBHandle;
+use control::crtc::Handle as CrtcHandle;
+use result::*;
+use ffi;
+
+/// A [`ResourceHandle`] for a plane.
+///
+/// [`ResourceHandle`]: ../ResourceHandle.t.html
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct Handle(control::RawHandle);
+
+impl ResourceHandle for Handle {
+    fn from_raw(raw: control::RawHandle) -> Self {
+        Handle(raw)
+    }
+
+    fn as_raw(&self) -> control::RawHandle {
+        self.0
+    }
+}
+
+impl control::property::LoadProperties for Handle {
+    const TYPE: u32 = ffi::DRM_MODE_OBJECT_PLANE;
+}
+
+/// A [`ResourceInfo`] for a plane.
+///
+/// [`ResourceInfo`]: ../ResourceInfo.t.html
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Info {
+    handle: Handle,
+    possible_crtcs: u32,
+    fb: FBHandle,
+    gamma_length: u32,
+    formats: Vec<u32>
+}
+
+impl Info {
+    /// A bitmask of the CRTCs this plane can be attached to, indexed the
+    /// same way as [`ResourceIds::crtcs`].
+    ///
+    /// [`ResourceIds::crtcs`]: ../ResourceIds.t.html#method.crtcs
+    pub fn possible_crtcs(&self) -> u32 { self.possible_crtcs }
+
+    /// The framebuffer currently attached to this plane.
+    pub fn fb(&self) -> FBHandle { self.fb }
+
+    /// The size of this plane's gamma lookup table.
+    pub fn gamma_length(&self) -> u32 { self.gamma_length }
+
+    /// The pixel formats this plane can scan out, as fourcc codes.
+    pub fn formats(&self) -> &[u32] { &self.formats }
+}
+
+impl ResourceInfo for Info {
+    type Handle = Handle;
+
+    fn load_from_device<T>(device: &T, handle: Handle) -> Result<Self>
+        where T: control::Device {
+
+        let mut raw: ffi::drm_mode_get_plane = Default::default();
+        raw.plane_id = handle.0;
+
+        unsafe {
+            try!(ffi::ioctl_mode_getplane(device.as_raw_fd(), &mut raw));
+        }
+
+        let mut formats = vec![0u32; raw.count_format_types as usize];
+        raw.format_type_ptr = formats.as_mut_ptr() as u64;
+
+        unsafe {
+            try!(ffi::ioctl_mode_getplane(device.as_raw_fd(), &mut raw));
+        }
+
+        Ok(Self {
+            handle: handle,
+            possible_crtcs: raw.possible_crtcs,
+            fb: FBHandle::from_raw(raw.fb_id),
+            gamma_length: raw.gamma_size,
+            formats: formats
+        })
+    }
+
+    fn handle(&self) -> Self::Handle { self.handle }
+}
+
+/// Enumerates the handles of every plane the device exposes.
+pub fn handles<T>(device: &T) -> Result<Vec<Handle>>
+    where T: control::Device {
+
+    let mut raw: ffi::drm_mode_get_plane_res = Default::default();
+    unsafe {
+        try!(ffi::ioctl_mode_getplaneresources(device.as_raw_fd(), &mut raw));
+    }
+
+    let mut plane_ids = vec![0u32; raw.count_planes as usize];
+    raw.plane_id_ptr = plane_ids.as_mut_ptr() as u64;
+
+    unsafe {
+        try!(ffi::ioctl_mode_getplaneresources(device.as_raw_fd(), &mut raw));
+    }
+
+    Ok(plane_ids.into_iter().map(Handle::from_raw).collect())
+}
+
+/// Attaches `fb` to `plane` and composites it onto `crtc`, without
+/// performing a full modeset.
+///
+/// `crtc_rect` is `(x, y, width, height)` in integer CRTC-space pixels.
+/// `src_rect` is the same shape but in 16.16 fixed-point framebuffer
+/// pixels, which lets the plane's source region be scaled or positioned
+/// sub-pixel. Passing a zero-valued `fb` disables the plane.
+pub fn set<T>(device: &T, plane: Handle, crtc: CrtcHandle, fb: FBHandle, flags: u32,
+              crtc_rect: (i32, i32, u32, u32), src_rect: (u32, u32, u32, u32)) -> Result<()>
+    where T: control::Device {
+
+    let mut raw: ffi::drm_mode_set_plane = Default::default();
+    raw.plane_id = plane.as_raw();
+    raw.crtc_id = crtc.as_raw();
+    raw.fb_id = fb.as_raw();
+    raw.flags = flags;
+    raw.crtc_x = crtc_rect.0;
+    raw.crtc_y = crtc_rect.1;
+    raw.crtc_w = crtc_rect.2;
+    raw.crtc_h = crtc_rect.3;
+    raw.src_x = src_rect.0;
+    raw.src_y = src_rect.1;
+    raw.src_w = src_rect.2;
+    raw.src_h = src_rect.3;
+
+    unsafe {
+        try!(ffi::ioctl_mode_setplane(device.as_raw_fd(), &mut raw));
+    }
+
+    Ok(())
+}
+
+impl ::std::fmt::Debug for Handle {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+        write!(f, "plane::Handle({})", self.0)
+    }
+}